@@ -1,8 +1,14 @@
-use rug::Integer;
+use crate::rate::Rate;
+use crate::rounding::{div_round, RoundingMode};
+use rug::{Integer, Rational};
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
 use std::fmt::Display;
-use std::ops::{Add, Sub};
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct USD {
     total_cents: Integer,
 }
@@ -24,10 +30,51 @@ impl USD {
         Self::from(dollars * 100 + cents)
     }
 
+    /// Parses worded amounts such as "15 dollars 31 cents", "15 dollars" or
+    /// "31 cents", ignoring case and an optional "and" between the dollars and
+    /// the cents.
+    pub fn parse_worded(s: &str) -> Result<Self, ParseUsdError> {
+        let words: Vec<String> = s.split_whitespace().map(str::to_lowercase).collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+        let (dollars, cents) = match words.as_slice() {
+            [] => return Err(ParseUsdError::Empty),
+            [d, "dollar" | "dollars"] => (Some(*d), None),
+            [c, "cent" | "cents"] => (None, Some(*c)),
+            [d, "dollar" | "dollars", c, "cent" | "cents"]
+            | [d, "dollar" | "dollars", "and", c, "cent" | "cents"] => (Some(*d), Some(*c)),
+            _ => return Err(ParseUsdError::UnrecognizedFormat),
+        };
+
+        let dollars = match dollars {
+            Some(d) if d.bytes().all(|b| b.is_ascii_digit()) => {
+                Integer::from_str(d).map_err(|_| ParseUsdError::InvalidDollars)?
+            }
+            Some(_) => return Err(ParseUsdError::InvalidDollars),
+            None => Integer::new(),
+        };
+        let cents = match cents {
+            Some(c) if c.bytes().all(|b| b.is_ascii_digit()) => {
+                Integer::from_str(c).map_err(|_| ParseUsdError::InvalidCents)?
+            }
+            Some(_) => return Err(ParseUsdError::InvalidCents),
+            None => Integer::new(),
+        };
+        if cents >= 100 {
+            return Err(ParseUsdError::CentsOutOfRange);
+        }
+
+        Ok(Self::from(dollars * 100 + cents))
+    }
+
     pub fn dollars(&self) -> Integer {
         self.total_cents.clone() / 100
     }
 
+    pub fn total_cents(&self) -> &Integer {
+        &self.total_cents
+    }
+
     pub fn cents(&self) -> u32 {
         let euclid_remainder = self.total_cents.mod_u(100);
         if self.total_cents < 0 && euclid_remainder != 0 {
@@ -37,6 +84,302 @@ impl USD {
         }
     }
 
+    /// Rounds to the nearest multiple of $10^`dollars_power`, e.g. a power of 2
+    /// rounds to the nearest $100.
+    pub fn quantize_to_power_of_ten(&self, dollars_power: u32, mode: RoundingMode) -> Self {
+        let step = Integer::from(Integer::u_pow_u(10, dollars_power)) * 100;
+        let steps = div_round(&self.total_cents, &step, mode);
+        Self::from(steps * step)
+    }
+
+    /// Rounds the magnitude to a whole dollar with `mode` and then takes off a
+    /// cent, so $20.40 becomes $19.99 rounding down or $20.99 rounding up. The
+    /// sign is reapplied afterwards, so negative amounts mirror positive ones.
+    /// Amounts already ending in .99 are left alone, and magnitudes that round
+    /// to $0 snap to $0.99, the smallest charm price.
+    pub fn snap_to_charm_price(&self, mode: RoundingMode) -> Self {
+        if self.cents() == 99 {
+            return self.clone();
+        }
+
+        let magnitude = Self::from(Integer::from(self.total_cents.abs_ref()));
+        let dollars = magnitude.quantize_to_power_of_ten(0, mode);
+        let snapped = if dollars.total_cents == 0 {
+            USD::new(0, 99)
+        } else {
+            dollars - USD::new(0, 1)
+        };
+
+        if self.total_cents < 0 {
+            -snapped
+        } else {
+            snapped
+        }
+    }
+
+    /// Splits a total evenly across `quantity` units, rounding the per-unit price
+    /// to the cent.
+    pub fn unit_price(&self, quantity: u64, mode: RoundingMode) -> Result<Self, DivByZero> {
+        if quantity == 0 {
+            return Err(DivByZero);
+        }
+
+        let cents = div_round(&self.total_cents, &Integer::from(quantity), mode);
+        Ok(Self::from(cents))
+    }
+
+    /// Returns (subtotal, surcharge, total) for presenting a flat surcharge as a
+    /// line item.
+    pub fn with_surcharge(&self, surcharge: &USD) -> (Self, Self, Self) {
+        (self.clone(), surcharge.clone(), self + surcharge)
+    }
+
+    /// One period's interest on this balance at `rate`, rounded to the cent.
+    pub fn period_interest(&self, rate: &Rate, mode: RoundingMode) -> Self {
+        self.scale(rate.value(), mode)
+    }
+
+    /// Applies each discount to the running amount in turn, rounding to the cent
+    /// after every step as a till would, so the result can differ by a cent from
+    /// applying the combined discount once.
+    pub fn apply_discount_chain(&self, discounts: &[Rate], mode: RoundingMode) -> Self {
+        discounts.iter().fold(self.clone(), |amount, discount| {
+            amount.scale(&Rational::from(1 - discount.value()), mode)
+        })
+    }
+
+    /// The number of shares that would carry an extra cent if this amount were
+    /// split `n` ways. Negative amounts are counted by magnitude.
+    ///
+    /// Saturates at `u32::MAX`, which only a share count above that can reach.
+    ///
+    /// Panics if `n` is zero.
+    pub fn split_remainder_cents(&self, n: usize) -> u32 {
+        assert!(n != 0, "cannot split an amount zero ways");
+        let remainder = Integer::from(self.total_cents.abs_ref()) % Integer::from(n);
+        remainder.to_u32().unwrap_or(u32::MAX)
+    }
+
+    /// How many whole units at `unit_price` this budget covers, saturating at
+    /// `u64::MAX`.
+    pub fn units_affordable(&self, unit_price: &USD) -> Result<u64, AffordabilityError> {
+        if unit_price.total_cents <= 0 {
+            return Err(AffordabilityError::NonPositivePrice);
+        }
+        if self.total_cents < 0 {
+            return Err(AffordabilityError::NegativeBudget);
+        }
+
+        let units = div_round(
+            &self.total_cents,
+            &unit_price.total_cents,
+            RoundingMode::Floor,
+        );
+        Ok(units.to_u64().unwrap_or(u64::MAX))
+    }
+
+    /// Formats the total cents zero-padded to `total_digits` digits for
+    /// fixed-field exports. The sign is not counted as a digit.
+    pub fn to_fixed_width(
+        &self,
+        total_digits: usize,
+        sign_style: SignStyle,
+    ) -> Result<String, FieldOverflow> {
+        let digits = Integer::from(self.total_cents.abs_ref()).to_string();
+        if digits.len() > total_digits {
+            return Err(FieldOverflow);
+        }
+
+        let negative = self.total_cents < 0;
+        let digits = format!("{digits:0>total_digits$}");
+        Ok(match sign_style {
+            SignStyle::LeadingPlusMinus => format!("{}{digits}", if negative { '-' } else { '+' }),
+            SignStyle::TrailingPlusMinus => format!("{digits}{}", if negative { '-' } else { '+' }),
+            SignStyle::TrailingCrDr => format!("{digits}{}", if negative { "CR" } else { "DR" }),
+        })
+    }
+
+    /// Splits this amount into `n` equal payments, rounded toward zero, with the
+    /// final payment absorbing the remainder so the payments sum exactly.
+    pub fn installments(&self, n: u32) -> Result<Vec<Self>, InstallmentError> {
+        let payment = self
+            .unit_price(n.into(), RoundingMode::TowardZero)
+            .map_err(|_| InstallmentError::ZeroInstallments)?;
+
+        let mut payments = vec![payment; n as usize];
+        let paid = Self::from(Integer::from(&payments[0].total_cents * (n - 1)));
+        payments[n as usize - 1] = self - paid;
+        debug_assert!(verify_split(self, &payments));
+        Ok(payments)
+    }
+
+    /// Distributes this amount in proportion to each holder's shares. Leftover
+    /// cents go to the holders with the largest remainders, earliest first on
+    /// ties, so the distributions sum exactly to this amount.
+    pub fn distribute_per_share(
+        &self,
+        shares: &[(String, u64)],
+    ) -> Result<Vec<(String, Self)>, DistributionError> {
+        let total_shares = shares
+            .iter()
+            .fold(Integer::new(), |total, (_, count)| total + count);
+        if total_shares == 0 {
+            return Err(DistributionError::ZeroTotalShares);
+        }
+
+        let pool = Integer::from(self.total_cents.abs_ref());
+        let mut parts = Vec::with_capacity(shares.len());
+        let mut remainders = Vec::with_capacity(shares.len());
+        for (i, (_, count)) in shares.iter().enumerate() {
+            let quota = Integer::from(&pool * *count);
+            let (part, remainder) = quota.div_rem_floor(total_shares.clone());
+            parts.push(part);
+            remainders.push((remainder, i));
+        }
+
+        let distributed = parts
+            .iter()
+            .fold(Integer::new(), |total, part| total + part);
+        let leftover = (pool - distributed)
+            .to_usize()
+            .expect("leftover is below share count");
+        remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for (_, i) in remainders.into_iter().take(leftover) {
+            parts[i] += 1;
+        }
+
+        let negative = self.total_cents < 0;
+        let distribution: Vec<(String, Self)> = shares
+            .iter()
+            .zip(parts)
+            .map(|((name, _), part)| {
+                let part = Self::from(part);
+                (name.clone(), if negative { -part } else { part })
+            })
+            .collect();
+        debug_assert!(verify_split(
+            self,
+            &distribution
+                .iter()
+                .map(|(_, part)| part.clone())
+                .collect::<Vec<_>>()
+        ));
+        Ok(distribution)
+    }
+
+    /// Breaks this amount's magnitude into denominations (in cents) using at most
+    /// the available count of each, preferring larger denominations. If exact
+    /// change can't be made, the error reports what is left after dispensing
+    /// greedily from the largest denomination down.
+    pub fn breakdown_with_inventory(
+        &self,
+        inventory: &BTreeMap<u32, u64>,
+    ) -> Result<BTreeMap<u32, u64>, InsufficientInventory> {
+        let stock: Vec<(Integer, u64)> = inventory
+            .iter()
+            .rev()
+            .filter(|(denomination, count)| **denomination > 0 && **count > 0)
+            .map(|(denomination, count)| (Integer::from(*denomination), *count))
+            .collect();
+        let amount = Integer::from(self.total_cents.abs_ref());
+
+        // The gcd and total value of each suffix of the stock.
+        let mut limits = vec![(Integer::new(), Integer::new()); stock.len()];
+        for (i, (denomination, available)) in stock.iter().enumerate().rev() {
+            let (gcd, value) = limits.get(i + 1).cloned().unwrap_or_default();
+            limits[i] = (
+                gcd.gcd(denomination),
+                value + Integer::from(denomination * *available),
+            );
+        }
+
+        let mut counts = vec![0; stock.len()];
+        let mut failed = HashSet::new();
+        if !Self::fill_from_stock(0, &amount, &stock, &limits, &mut counts, &mut failed) {
+            let mut shortfall = amount;
+            for (denomination, available) in &stock {
+                let used = Integer::from(&shortfall / denomination);
+                let used = std::cmp::min(used, Integer::from(*available));
+                shortfall -= used * denomination;
+            }
+            return Err(InsufficientInventory {
+                shortfall: Self::from(shortfall),
+            });
+        }
+
+        Ok(stock
+            .iter()
+            .zip(counts)
+            .filter(|(_, count)| *count > 0)
+            .map(|((denomination, _), count)| {
+                (
+                    denomination.to_u32().expect("denomination came from a u32"),
+                    count,
+                )
+            })
+            .collect())
+    }
+
+    /// Finds counts for `stock[level..]` that make up `remaining`. Each level
+    /// is pruned by the gcd and total value of the denominations left, and
+    /// `(level, remaining)` states already known to fail are skipped, which
+    /// keeps impossible amounts from searching every combination.
+    fn fill_from_stock(
+        level: usize,
+        remaining: &Integer,
+        stock: &[(Integer, u64)],
+        limits: &[(Integer, Integer)],
+        counts: &mut [u64],
+        failed: &mut HashSet<(usize, Integer)>,
+    ) -> bool {
+        if *remaining == 0 {
+            return true;
+        }
+        let Some((denomination, available)) = stock.get(level) else {
+            return false;
+        };
+        let (gcd, value) = &limits[level];
+        if remaining > value
+            || !remaining.is_divisible(gcd)
+            || failed.contains(&(level, remaining.clone()))
+        {
+            return false;
+        }
+
+        let fits = Integer::from(remaining / denomination);
+        let most = std::cmp::min(fits, Integer::from(*available))
+            .to_u64()
+            .expect("bounded by a u64 count");
+        for count in (0..=most).rev() {
+            let left = remaining - Integer::from(denomination * count);
+            if let Some((_, rest_value)) = limits.get(level + 1) {
+                if left > *rest_value {
+                    break;
+                }
+            }
+            if Self::fill_from_stock(level + 1, &left, stock, limits, counts, failed) {
+                counts[level] = count;
+                return true;
+            }
+        }
+
+        failed.insert((level, remaining.clone()));
+        false
+    }
+
+    /// Whether `other` is this amount with its sign flipped. Zero never counts.
+    pub fn is_sign_flip_of(&self, other: &USD) -> bool {
+        self.total_cents != 0 && *self == -other
+    }
+
+    pub(crate) fn scale(&self, factor: &Rational, mode: RoundingMode) -> Self {
+        Self::from_rational_cents(&Rational::from(&self.total_cents * factor), mode)
+    }
+
+    pub(crate) fn from_rational_cents(cents: &Rational, mode: RoundingMode) -> Self {
+        Self::from(div_round(cents.numer(), cents.denom(), mode))
+    }
+
     fn _add(&self, other: &Self) -> Self {
         let result = &self.total_cents + &other.total_cents;
         Self::from(Integer::from(result))
@@ -48,6 +391,80 @@ impl USD {
     }
 }
 
+/// Sign representations for `USD::to_fixed_width`. `TrailingCrDr` marks
+/// negative amounts `CR` and all others `DR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignStyle {
+    LeadingPlusMinus,
+    TrailingPlusMinus,
+    TrailingCrDr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldOverflow;
+
+impl Display for FieldOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "amount does not fit in the field width")
+    }
+}
+
+impl Error for FieldOverflow {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallmentError {
+    ZeroInstallments,
+}
+
+impl Display for InstallmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstallmentError::ZeroInstallments => write!(f, "need at least one installment"),
+        }
+    }
+}
+
+impl Error for InstallmentError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistributionError {
+    ZeroTotalShares,
+}
+
+impl Display for DistributionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DistributionError::ZeroTotalShares => write!(f, "no shares to distribute across"),
+        }
+    }
+}
+
+impl Error for DistributionError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsufficientInventory {
+    pub shortfall: USD,
+}
+
+impl Display for InsufficientInventory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cannot make exact change, {} short", self.shortfall)
+    }
+}
+
+impl Error for InsufficientInventory {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivByZero;
+
+impl Display for DivByZero {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "attempted to divide an amount by zero")
+    }
+}
+
+impl Error for DivByZero {}
+
 impl From<Integer> for USD {
     fn from(total_cents: Integer) -> Self {
         Self { total_cents }
@@ -56,11 +473,117 @@ impl From<Integer> for USD {
 
 impl Display for USD {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let sign = if self.dollars() < 0 { "-" } else { "" };
+        let sign = if self.total_cents < 0 { "-" } else { "" };
         write!(f, "{}${}.{:02}", sign, self.dollars().abs(), self.cents())
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AffordabilityError {
+    NonPositivePrice,
+    NegativeBudget,
+}
+
+impl Display for AffordabilityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AffordabilityError::NonPositivePrice => write!(f, "unit price must be positive"),
+            AffordabilityError::NegativeBudget => write!(f, "budget must not be negative"),
+        }
+    }
+}
+
+impl Error for AffordabilityError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseUsdError {
+    Empty,
+    InvalidDollars,
+    InvalidCents,
+    CentsOutOfRange,
+    UnrecognizedFormat,
+}
+
+impl Display for ParseUsdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseUsdError::Empty => write!(f, "no amount given"),
+            ParseUsdError::InvalidDollars => write!(f, "invalid dollar amount"),
+            ParseUsdError::InvalidCents => write!(f, "cents must be one or two digits"),
+            ParseUsdError::CentsOutOfRange => write!(f, "cents must be less than 100"),
+            ParseUsdError::UnrecognizedFormat => write!(f, "unrecognized amount format"),
+        }
+    }
+}
+
+impl Error for ParseUsdError {}
+
+/// Parses amounts in the `Display` format, e.g. `$15.30` or `-$0.50`. The `$`
+/// and the cents are optional, and a single cents digit means tens of cents.
+impl FromStr for USD {
+    type Err = ParseUsdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let s = s.strip_prefix('$').unwrap_or(s);
+        if s.is_empty() {
+            return Err(ParseUsdError::Empty);
+        }
+
+        let (dollars, cents) = match s.split_once('.') {
+            Some((dollars, cents)) => (dollars, Some(cents)),
+            None => (s, None),
+        };
+
+        if dollars.is_empty() || !dollars.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseUsdError::InvalidDollars);
+        }
+        let dollars = Integer::from_str(dollars).map_err(|_| ParseUsdError::InvalidDollars)?;
+
+        let cents = match cents {
+            None => 0,
+            Some(c) if !(1..=2).contains(&c.len()) || !c.bytes().all(|b| b.is_ascii_digit()) => {
+                return Err(ParseUsdError::InvalidCents);
+            }
+            Some(c) if c.len() == 1 => c.parse::<u32>().unwrap() * 10,
+            Some(c) => c.parse::<u32>().unwrap(),
+        };
+
+        let total_cents: Integer = dollars * 100 + cents;
+        Ok(Self::from(if negative {
+            -total_cents
+        } else {
+            total_cents
+        }))
+    }
+}
+
+/// Whether `parts` sum exactly to `whole`.
+pub fn verify_split(whole: &USD, parts: &[USD]) -> bool {
+    let total = parts
+        .iter()
+        .fold(Integer::new(), |total, part| total + &part.total_cents);
+    total == whole.total_cents
+}
+
+/// Parses every string, returning the amounts that parsed alongside the index
+/// and error of each one that didn't.
+pub fn parse_many(strings: &[&str]) -> (Vec<USD>, Vec<(usize, ParseUsdError)>) {
+    let mut amounts = Vec::new();
+    let mut errors = Vec::new();
+    for (i, s) in strings.iter().enumerate() {
+        match s.parse() {
+            Ok(amount) => amounts.push(amount),
+            Err(e) => errors.push((i, e)),
+        }
+    }
+    (amounts, errors)
+}
+
 impl Add<&USD> for &USD {
     type Output = USD;
 
@@ -125,6 +648,22 @@ impl Sub<USD> for USD {
     }
 }
 
+impl Neg for &USD {
+    type Output = USD;
+
+    fn neg(self) -> USD {
+        USD::from(Integer::from(-&self.total_cents))
+    }
+}
+
+impl Neg for USD {
+    type Output = USD;
+
+    fn neg(self) -> USD {
+        USD::from(-self.total_cents)
+    }
+}
+
 #[cfg(test)]
 mod usd_creation_tests {
     use super::*;
@@ -263,6 +802,18 @@ mod usd_ops_tests {
         assert_eq!(98, c3.cents());
     }
 
+    #[test]
+    fn negate_positive_and_negative() {
+        let c = USD::new(15, 31);
+        assert_eq!(USD::new(-15, 31), -&c);
+        assert_eq!(c, -USD::new(-15, 31));
+    }
+
+    #[test]
+    fn negate_0() {
+        assert_eq!(USD::new(0, 0), -USD::new(0, 0));
+    }
+
     #[test]
     fn subtract_negative_with_carry() {
         let c1 = USD::new(9, 83);
@@ -362,6 +913,7 @@ mod usd_order_tests {
     }
 
     #[test]
+    #[allow(clippy::nonminimal_bool)]
     fn should_order_equivalent_values() {
         let c1 = USD::new(42, 0);
         let c2 = USD::new(42, 0);
@@ -374,6 +926,7 @@ mod usd_order_tests {
 }
 
 #[cfg(test)]
+#[allow(clippy::inconsistent_digit_grouping)]
 mod usd_conversion_tests {
     use super::*;
 
@@ -447,4 +1000,866 @@ mod usd_display_tests {
         let c = USD::new(-300, 16);
         assert_eq!("-$300.16", c.to_string());
     }
+
+    #[test]
+    fn should_print_sign_for_negative_sub_dollar_amount() {
+        let c = USD::from(Integer::from(-50));
+        assert_eq!("-$0.50", c.to_string());
+    }
+
+    #[test]
+    fn should_print_sign_for_negative_single_cent() {
+        let c = USD::from(Integer::from(-1));
+        assert_eq!("-$0.01", c.to_string());
+    }
+}
+
+#[cfg(test)]
+mod usd_quantize_tests {
+    use super::*;
+
+    #[test]
+    fn should_quantize_positive_to_tens() {
+        let c = USD::new(1234, 56);
+        assert_eq!(
+            USD::new(1230, 0),
+            c.quantize_to_power_of_ten(1, RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(1240, 0),
+            c.quantize_to_power_of_ten(1, RoundingMode::Ceiling)
+        );
+    }
+
+    #[test]
+    fn should_quantize_positive_to_hundreds() {
+        let c = USD::new(1234, 56);
+        assert_eq!(
+            USD::new(1200, 0),
+            c.quantize_to_power_of_ten(2, RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn should_quantize_positive_to_thousands() {
+        let c = USD::new(1234, 56);
+        assert_eq!(
+            USD::new(1000, 0),
+            c.quantize_to_power_of_ten(3, RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(2000, 0),
+            c.quantize_to_power_of_ten(3, RoundingMode::Ceiling)
+        );
+    }
+
+    #[test]
+    fn should_quantize_negative_to_tens() {
+        let c = USD::new(-1234, 56);
+        assert_eq!(
+            USD::new(-1230, 0),
+            c.quantize_to_power_of_ten(1, RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(-1240, 0),
+            c.quantize_to_power_of_ten(1, RoundingMode::Floor)
+        );
+    }
+
+    #[test]
+    fn should_quantize_negative_to_hundreds() {
+        let c = USD::new(-1234, 56);
+        assert_eq!(
+            USD::new(-1200, 0),
+            c.quantize_to_power_of_ten(2, RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn should_quantize_negative_to_thousands() {
+        let c = USD::new(-1234, 56);
+        assert_eq!(
+            USD::new(-1000, 0),
+            c.quantize_to_power_of_ten(3, RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(-2000, 0),
+            c.quantize_to_power_of_ten(3, RoundingMode::Floor)
+        );
+    }
+
+    #[test]
+    fn should_round_ties_away_from_zero_with_half_up() {
+        let c = USD::new(-1250, 0);
+        assert_eq!(
+            USD::new(-1300, 0),
+            c.quantize_to_power_of_ten(2, RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(-1200, 0),
+            c.quantize_to_power_of_ten(2, RoundingMode::HalfEven)
+        );
+    }
+}
+
+#[cfg(test)]
+mod usd_unit_price_tests {
+    use super::*;
+
+    #[test]
+    fn should_round_uneven_unit_price() {
+        let c = USD::new(10, 0);
+        assert_eq!(Ok(USD::new(3, 33)), c.unit_price(3, RoundingMode::HalfUp));
+        assert_eq!(Ok(USD::new(3, 34)), c.unit_price(3, RoundingMode::Ceiling));
+    }
+
+    #[test]
+    fn should_split_negative_total() {
+        let c = USD::new(-10, 0);
+        assert_eq!(Ok(USD::new(-2, 50)), c.unit_price(4, RoundingMode::HalfUp));
+    }
+
+    #[test]
+    fn single_unit_returns_total() {
+        let c = USD::new(42, 17);
+        assert_eq!(Ok(USD::new(42, 17)), c.unit_price(1, RoundingMode::Floor));
+    }
+
+    #[test]
+    fn zero_quantity_errors() {
+        let c = USD::new(10, 0);
+        assert_eq!(Err(DivByZero), c.unit_price(0, RoundingMode::HalfUp));
+    }
+}
+
+#[cfg(test)]
+mod usd_surcharge_tests {
+    use super::*;
+
+    #[test]
+    fn total_should_be_subtotal_plus_surcharge() {
+        let (subtotal, surcharge, total) = USD::new(48, 75).with_surcharge(&USD::new(2, 50));
+        assert_eq!(USD::new(48, 75), subtotal);
+        assert_eq!(USD::new(2, 50), surcharge);
+        assert_eq!(USD::new(51, 25), total);
+        assert_eq!(&subtotal + &surcharge, total);
+    }
+
+    #[test]
+    fn zero_surcharge_leaves_total_unchanged() {
+        let (subtotal, surcharge, total) = USD::new(19, 99).with_surcharge(&USD::new(0, 0));
+        assert_eq!(USD::new(0, 0), surcharge);
+        assert_eq!(subtotal, total);
+    }
+}
+
+#[cfg(test)]
+mod usd_discount_tests {
+    use super::*;
+
+    #[test]
+    fn should_apply_discounts_sequentially() {
+        let c = USD::new(100, 0);
+        let discounts = [Rate::percent(20), Rate::percent(10)];
+        assert_eq!(
+            USD::new(72, 0),
+            c.apply_discount_chain(&discounts, RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn empty_chain_leaves_amount_unchanged() {
+        let c = USD::new(59, 99);
+        assert_eq!(c, c.apply_discount_chain(&[], RoundingMode::HalfUp));
+    }
+
+    #[test]
+    fn should_round_after_each_discount() {
+        // Each half of $0.01 rounds back up to $0.01, whereas a single 75%
+        // discount would leave $0.0025 and round down to $0.00.
+        let c = USD::new(0, 1);
+        let discounts = [Rate::percent(50), Rate::percent(50)];
+        assert_eq!(
+            USD::new(0, 1),
+            c.apply_discount_chain(&discounts, RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(0, 0),
+            c.apply_discount_chain(&[Rate::percent(75)], RoundingMode::HalfUp)
+        );
+    }
+}
+
+#[cfg(test)]
+mod usd_split_remainder_tests {
+    use super::*;
+
+    #[test]
+    fn should_count_leftover_cents() {
+        assert_eq!(1, USD::new(10, 0).split_remainder_cents(3));
+        assert_eq!(6, USD::new(10, 0).split_remainder_cents(7));
+    }
+
+    #[test]
+    fn even_split_has_no_remainder() {
+        assert_eq!(0, USD::new(9, 0).split_remainder_cents(3));
+    }
+
+    #[test]
+    fn negative_amount_uses_magnitude() {
+        assert_eq!(1, USD::new(-10, 0).split_remainder_cents(3));
+    }
+
+    #[test]
+    fn share_count_beyond_u32_is_accepted() {
+        assert_eq!(1000, USD::new(10, 0).split_remainder_cents(5_000_000_000));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_shares_panics() {
+        USD::new(10, 0).split_remainder_cents(0);
+    }
+}
+
+#[cfg(test)]
+mod usd_parse_tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_display_format() {
+        assert_eq!(Ok(USD::new(15, 30)), "$15.30".parse());
+        assert_eq!(Ok(USD::new(-300, 16)), "-$300.16".parse());
+        assert_eq!(Ok(USD::from(Integer::from(-50))), "-$0.50".parse());
+    }
+
+    #[test]
+    fn should_parse_without_symbol_or_cents() {
+        assert_eq!(Ok(USD::new(42, 0)), "42".parse());
+        assert_eq!(Ok(USD::new(42, 50)), "42.5".parse());
+        assert_eq!(Ok(USD::new(-7, 5)), " -7.05 ".parse());
+    }
+
+    #[test]
+    fn should_round_trip_display() {
+        for c in [USD::new(0, 0), USD::new(3_705, 7), USD::new(-10_513_012, 3)] {
+            assert_eq!(Ok(c.clone()), c.to_string().parse());
+        }
+    }
+
+    #[test]
+    fn should_reject_malformed_amounts() {
+        assert_eq!(Err(ParseUsdError::Empty), "".parse::<USD>());
+        assert_eq!(Err(ParseUsdError::Empty), "-$".parse::<USD>());
+        assert_eq!(Err(ParseUsdError::InvalidDollars), "$1,000".parse::<USD>());
+        assert_eq!(Err(ParseUsdError::InvalidDollars), "$.50".parse::<USD>());
+        assert_eq!(Err(ParseUsdError::InvalidDollars), "--5".parse::<USD>());
+        assert_eq!(Err(ParseUsdError::InvalidCents), "$1.234".parse::<USD>());
+        assert_eq!(Err(ParseUsdError::InvalidCents), "$1.".parse::<USD>());
+        assert_eq!(Err(ParseUsdError::InvalidCents), "$1.-5".parse::<USD>());
+    }
+}
+
+#[cfg(test)]
+mod parse_many_tests {
+    use super::*;
+
+    #[test]
+    fn should_collect_amounts_and_errors() {
+        let (amounts, errors) = parse_many(&["$1.00", "abc", "-$2.50", "", "3.5"]);
+        assert_eq!(
+            vec![USD::new(1, 0), USD::new(-2, 50), USD::new(3, 50)],
+            amounts
+        );
+        assert_eq!(
+            vec![
+                (1, ParseUsdError::InvalidDollars),
+                (3, ParseUsdError::Empty)
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn all_valid_has_no_errors() {
+        let (amounts, errors) = parse_many(&["1", "2"]);
+        assert_eq!(2, amounts.len());
+        assert!(errors.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod usd_affordability_tests {
+    use super::*;
+
+    #[test]
+    fn should_floor_units() {
+        assert_eq!(Ok(4), USD::new(100, 0).units_affordable(&USD::new(24, 99)));
+    }
+
+    #[test]
+    fn exact_budget_buys_whole_units() {
+        assert_eq!(Ok(4), USD::new(100, 0).units_affordable(&USD::new(25, 0)));
+    }
+
+    #[test]
+    fn zero_budget_affords_nothing() {
+        assert_eq!(Ok(0), USD::new(0, 0).units_affordable(&USD::new(24, 99)));
+    }
+
+    #[test]
+    fn zero_or_negative_price_errors() {
+        let budget = USD::new(100, 0);
+        assert_eq!(
+            Err(AffordabilityError::NonPositivePrice),
+            budget.units_affordable(&USD::new(0, 0))
+        );
+        assert_eq!(
+            Err(AffordabilityError::NonPositivePrice),
+            budget.units_affordable(&USD::new(-5, 0))
+        );
+    }
+
+    #[test]
+    fn negative_budget_errors() {
+        assert_eq!(
+            Err(AffordabilityError::NegativeBudget),
+            USD::new(-1, 0).units_affordable(&USD::new(5, 0))
+        );
+    }
+}
+
+#[cfg(test)]
+mod usd_fixed_width_tests {
+    use super::*;
+
+    #[test]
+    fn should_pad_positive_value() {
+        let c = USD::new(123, 45);
+        assert_eq!(
+            Ok("+0000012345".to_string()),
+            c.to_fixed_width(10, SignStyle::LeadingPlusMinus)
+        );
+        assert_eq!(
+            Ok("0000012345+".to_string()),
+            c.to_fixed_width(10, SignStyle::TrailingPlusMinus)
+        );
+        assert_eq!(
+            Ok("0000012345DR".to_string()),
+            c.to_fixed_width(10, SignStyle::TrailingCrDr)
+        );
+    }
+
+    #[test]
+    fn should_pad_negative_value() {
+        let c = USD::new(-123, 45);
+        assert_eq!(
+            Ok("-0000012345".to_string()),
+            c.to_fixed_width(10, SignStyle::LeadingPlusMinus)
+        );
+        assert_eq!(
+            Ok("0000012345-".to_string()),
+            c.to_fixed_width(10, SignStyle::TrailingPlusMinus)
+        );
+        assert_eq!(
+            Ok("0000012345CR".to_string()),
+            c.to_fixed_width(10, SignStyle::TrailingCrDr)
+        );
+    }
+
+    #[test]
+    fn should_fill_field_exactly() {
+        let c = USD::new(99_999_999, 99);
+        assert_eq!(
+            Ok("+9999999999".to_string()),
+            c.to_fixed_width(10, SignStyle::LeadingPlusMinus)
+        );
+    }
+
+    #[test]
+    fn should_error_when_value_overflows_field() {
+        let c = USD::new(100_000_000, 0);
+        assert_eq!(
+            Err(FieldOverflow),
+            c.to_fixed_width(10, SignStyle::LeadingPlusMinus)
+        );
+    }
+}
+
+#[cfg(test)]
+mod usd_sign_flip_tests {
+    use super::*;
+
+    #[test]
+    fn opposite_amounts_are_sign_flips() {
+        let c1 = USD::new(50, 0);
+        let c2 = USD::new(-50, 0);
+        assert!(c1.is_sign_flip_of(&c2));
+        assert!(c2.is_sign_flip_of(&c1));
+    }
+
+    #[test]
+    fn equal_amounts_are_not_sign_flips() {
+        let c = USD::new(50, 0);
+        assert!(!c.is_sign_flip_of(&c));
+    }
+
+    #[test]
+    fn different_magnitudes_are_not_sign_flips() {
+        assert!(!USD::new(50, 0).is_sign_flip_of(&USD::new(-50, 1)));
+    }
+
+    #[test]
+    fn zero_is_not_a_sign_flip() {
+        let c = USD::new(0, 0);
+        assert!(!c.is_sign_flip_of(&c));
+    }
+}
+
+#[cfg(test)]
+mod usd_installment_tests {
+    use super::*;
+
+    #[test]
+    fn final_installment_absorbs_remainder() {
+        let payments = USD::new(100, 0).installments(3).unwrap();
+        assert_eq!(
+            vec![USD::new(33, 33), USD::new(33, 33), USD::new(33, 34)],
+            payments
+        );
+    }
+
+    #[test]
+    fn even_installments_are_equal() {
+        let payments = USD::new(90, 0).installments(3).unwrap();
+        assert_eq!(vec![USD::new(30, 0); 3], payments);
+    }
+
+    #[test]
+    fn negative_remainder_goes_to_final_installment() {
+        let payments = USD::new(-100, 0).installments(3).unwrap();
+        assert_eq!(
+            vec![USD::new(-33, 33), USD::new(-33, 33), USD::new(-33, 34)],
+            payments
+        );
+    }
+
+    #[test]
+    fn single_installment_is_whole_amount() {
+        let c = USD::new(12, 34);
+        assert_eq!(Ok(vec![c.clone()]), c.installments(1));
+    }
+
+    #[test]
+    fn zero_installments_errors() {
+        assert_eq!(
+            Err(InstallmentError::ZeroInstallments),
+            USD::new(100, 0).installments(0)
+        );
+    }
+}
+
+#[cfg(test)]
+mod usd_charm_price_tests {
+    use super::*;
+
+    #[test]
+    fn should_snap_below_nearest_dollar() {
+        assert_eq!(
+            USD::new(19, 99),
+            USD::new(20, 40).snap_to_charm_price(RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(20, 99),
+            USD::new(20, 60).snap_to_charm_price(RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn rounding_direction_picks_dollar() {
+        let c = USD::new(20, 40);
+        assert_eq!(
+            USD::new(20, 99),
+            c.snap_to_charm_price(RoundingMode::Ceiling)
+        );
+        assert_eq!(USD::new(19, 99), c.snap_to_charm_price(RoundingMode::Floor));
+    }
+
+    #[test]
+    fn existing_charm_price_is_unchanged() {
+        let c = USD::new(19, 99);
+        assert_eq!(c, c.snap_to_charm_price(RoundingMode::Floor));
+        assert_eq!(c, c.snap_to_charm_price(RoundingMode::Ceiling));
+        let c = USD::new(-19, 99);
+        assert_eq!(c, c.snap_to_charm_price(RoundingMode::Floor));
+    }
+
+    #[test]
+    fn sub_dollar_amounts_snap_to_99_cents() {
+        assert_eq!(
+            USD::new(0, 99),
+            USD::new(0, 0).snap_to_charm_price(RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(0, 99),
+            USD::new(0, 30).snap_to_charm_price(RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(0, 99),
+            USD::new(0, 70).snap_to_charm_price(RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn negative_amounts_mirror_positive() {
+        let c = USD::new(-20, 40);
+        assert_eq!(
+            USD::new(-20, 99),
+            c.snap_to_charm_price(RoundingMode::Ceiling)
+        );
+        assert_eq!(
+            USD::new(-19, 99),
+            c.snap_to_charm_price(RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::from(Integer::from(-99)),
+            USD::from(Integer::from(-30)).snap_to_charm_price(RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn whole_dollar_snaps_down_a_cent() {
+        let c = USD::new(20, 0);
+        assert_eq!(
+            USD::new(19, 99),
+            c.snap_to_charm_price(RoundingMode::Ceiling)
+        );
+        assert_eq!(USD::new(19, 99), c.snap_to_charm_price(RoundingMode::Floor));
+    }
+
+    #[test]
+    fn charm_price_is_stable() {
+        let c = USD::new(19, 99);
+        assert_eq!(c, c.snap_to_charm_price(RoundingMode::HalfUp));
+        assert_eq!(c, c.snap_to_charm_price(RoundingMode::Ceiling));
+    }
+}
+
+#[cfg(test)]
+mod usd_distribution_tests {
+    use super::*;
+
+    fn holders(counts: &[u64]) -> Vec<(String, u64)> {
+        counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| (format!("holder{i}"), *count))
+            .collect()
+    }
+
+    fn total(distribution: &[(String, USD)]) -> USD {
+        distribution
+            .iter()
+            .fold(USD::new(0, 0), |sum, (_, amount)| sum + amount)
+    }
+
+    #[test]
+    fn should_distribute_proportionally() {
+        let pool = USD::new(100, 0);
+        let distribution = pool.distribute_per_share(&holders(&[70, 30])).unwrap();
+        assert_eq!(
+            vec![
+                ("holder0".to_string(), USD::new(70, 0)),
+                ("holder1".to_string(), USD::new(30, 0)),
+            ],
+            distribution
+        );
+        assert_eq!(pool, total(&distribution));
+    }
+
+    #[test]
+    fn leftover_cent_goes_to_earliest_holder_on_tie() {
+        let pool = USD::new(100, 0);
+        let distribution = pool.distribute_per_share(&holders(&[1, 1, 1])).unwrap();
+        let amounts: Vec<USD> = distribution.iter().map(|(_, a)| a.clone()).collect();
+        assert_eq!(
+            vec![USD::new(33, 34), USD::new(33, 33), USD::new(33, 33)],
+            amounts
+        );
+        assert_eq!(pool, total(&distribution));
+    }
+
+    #[test]
+    fn leftover_cents_go_to_largest_remainders() {
+        // Quotas of 1.43, 2.86 and 5.71 cents.
+        let pool = USD::new(0, 10);
+        let distribution = pool.distribute_per_share(&holders(&[1, 2, 4])).unwrap();
+        let amounts: Vec<USD> = distribution.iter().map(|(_, a)| a.clone()).collect();
+        assert_eq!(
+            vec![USD::new(0, 1), USD::new(0, 3), USD::new(0, 6)],
+            amounts
+        );
+    }
+
+    #[test]
+    fn should_distribute_negative_pool() {
+        let pool = USD::new(-100, 0);
+        let distribution = pool.distribute_per_share(&holders(&[1, 1, 1])).unwrap();
+        assert_eq!(USD::new(-33, 34), distribution[0].1);
+        assert_eq!(pool, total(&distribution));
+    }
+
+    #[test]
+    fn zero_total_shares_errors() {
+        let pool = USD::new(100, 0);
+        assert_eq!(
+            Err(DistributionError::ZeroTotalShares),
+            pool.distribute_per_share(&holders(&[0, 0]))
+        );
+        assert_eq!(
+            Err(DistributionError::ZeroTotalShares),
+            pool.distribute_per_share(&[])
+        );
+    }
+}
+
+#[cfg(test)]
+mod usd_parse_worded_tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_dollars_and_cents() {
+        assert_eq!(
+            Ok(USD::new(15, 31)),
+            USD::parse_worded("15 dollars 31 cents")
+        );
+        assert_eq!(
+            Ok(USD::new(15, 31)),
+            USD::parse_worded("15 Dollars and 31 CENTS")
+        );
+    }
+
+    #[test]
+    fn should_parse_dollars_only() {
+        assert_eq!(Ok(USD::new(15, 0)), USD::parse_worded("15 dollars"));
+        assert_eq!(Ok(USD::new(1, 0)), USD::parse_worded("1 dollar"));
+    }
+
+    #[test]
+    fn should_parse_cents_only() {
+        assert_eq!(Ok(USD::new(0, 31)), USD::parse_worded("31 cents"));
+        assert_eq!(Ok(USD::new(0, 1)), USD::parse_worded("1 cent"));
+    }
+
+    #[test]
+    fn should_reject_cents_of_100_or_more() {
+        assert_eq!(
+            Err(ParseUsdError::CentsOutOfRange),
+            USD::parse_worded("15 dollars 100 cents")
+        );
+    }
+
+    #[test]
+    fn should_reject_unparseable_strings() {
+        assert_eq!(Err(ParseUsdError::Empty), USD::parse_worded("  "));
+        assert_eq!(
+            Err(ParseUsdError::UnrecognizedFormat),
+            USD::parse_worded("fifteen bucks")
+        );
+        assert_eq!(
+            Err(ParseUsdError::UnrecognizedFormat),
+            USD::parse_worded("31 cents 15 dollars")
+        );
+        assert_eq!(
+            Err(ParseUsdError::InvalidDollars),
+            USD::parse_worded("-15 dollars")
+        );
+        assert_eq!(
+            Err(ParseUsdError::InvalidCents),
+            USD::parse_worded("15 dollars x cents")
+        );
+    }
+
+    #[test]
+    fn should_require_digits_for_cents() {
+        assert_eq!(
+            Err(ParseUsdError::InvalidCents),
+            USD::parse_worded("+5 cents")
+        );
+        assert_eq!(
+            Err(ParseUsdError::InvalidCents),
+            USD::parse_worded("1 dollar +5 cents")
+        );
+        assert_eq!(
+            Err(ParseUsdError::CentsOutOfRange),
+            USD::parse_worded("99999999999 cents")
+        );
+    }
+
+    #[test]
+    fn and_is_only_allowed_between_dollars_and_cents() {
+        assert_eq!(
+            Err(ParseUsdError::UnrecognizedFormat),
+            USD::parse_worded("1 and dollars")
+        );
+        assert_eq!(
+            Err(ParseUsdError::UnrecognizedFormat),
+            USD::parse_worded("and 31 cents")
+        );
+        assert_eq!(
+            Err(ParseUsdError::UnrecognizedFormat),
+            USD::parse_worded("15 dollars and and 31 cents")
+        );
+    }
+}
+
+#[cfg(test)]
+mod usd_interest_tests {
+    use super::*;
+
+    #[test]
+    fn should_charge_rate_on_balance() {
+        let c = USD::new(1_000, 0);
+        assert_eq!(
+            USD::new(50, 0),
+            c.period_interest(&Rate::percent(5), RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(5, 0),
+            c.period_interest(&Rate::new(5, 1_000), RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn should_round_interest_to_cents() {
+        let c = USD::new(333, 33);
+        assert_eq!(
+            USD::new(16, 67),
+            c.period_interest(&Rate::percent(5), RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            USD::new(16, 66),
+            c.period_interest(&Rate::percent(5), RoundingMode::Floor)
+        );
+    }
+
+    #[test]
+    fn negative_balance_has_negative_interest() {
+        let c = USD::new(-1_000, 0);
+        assert_eq!(
+            USD::new(-50, 0),
+            c.period_interest(&Rate::percent(5), RoundingMode::HalfUp)
+        );
+    }
+}
+
+#[cfg(test)]
+mod verify_split_tests {
+    use super::*;
+
+    #[test]
+    fn exact_split_verifies() {
+        let parts = [USD::new(33, 33), USD::new(33, 33), USD::new(33, 34)];
+        assert!(verify_split(&USD::new(100, 0), &parts));
+    }
+
+    #[test]
+    fn off_by_a_cent_split_fails() {
+        let parts = [USD::new(33, 33), USD::new(33, 33), USD::new(33, 33)];
+        assert!(!verify_split(&USD::new(100, 0), &parts));
+    }
+
+    #[test]
+    fn empty_parts_only_split_zero() {
+        assert!(!verify_split(&USD::new(100, 0), &[]));
+        assert!(verify_split(&USD::new(0, 0), &[]));
+    }
+}
+
+#[cfg(test)]
+mod usd_breakdown_tests {
+    use super::*;
+
+    fn drawer(entries: &[(u32, u64)]) -> BTreeMap<u32, u64> {
+        entries.iter().copied().collect()
+    }
+
+    #[test]
+    fn should_prefer_larger_denominations() {
+        let inventory = drawer(&[(1, 10), (5, 5), (10, 5), (25, 5), (100, 2)]);
+        assert_eq!(
+            Ok(drawer(&[(100, 1), (25, 2), (10, 1), (5, 1)])),
+            USD::new(1, 65).breakdown_with_inventory(&inventory)
+        );
+    }
+
+    #[test]
+    fn should_respect_available_counts() {
+        let inventory = drawer(&[(1, 10), (5, 5), (25, 1)]);
+        assert_eq!(
+            Ok(drawer(&[(25, 1), (5, 5), (1, 2)])),
+            USD::new(0, 52).breakdown_with_inventory(&inventory)
+        );
+    }
+
+    #[test]
+    fn depleted_small_coins_force_other_denominations() {
+        // Greedy would take a quarter and need a nickel that isn't there.
+        let inventory = drawer(&[(5, 0), (10, 3), (25, 2)]);
+        assert_eq!(
+            Ok(drawer(&[(10, 3)])),
+            USD::new(0, 30).breakdown_with_inventory(&inventory)
+        );
+    }
+
+    #[test]
+    fn should_error_with_shortfall_when_impossible() {
+        let inventory = drawer(&[(5, 1), (10, 4), (25, 4)]);
+        assert_eq!(
+            Err(InsufficientInventory {
+                shortfall: USD::new(0, 2)
+            }),
+            USD::new(0, 7).breakdown_with_inventory(&inventory)
+        );
+    }
+
+    #[test]
+    fn should_error_when_stock_runs_out() {
+        let inventory = drawer(&[(100, 1), (25, 1)]);
+        assert_eq!(
+            Err(InsufficientInventory {
+                shortfall: USD::new(0, 75)
+            }),
+            USD::new(2, 0).breakdown_with_inventory(&inventory)
+        );
+    }
+
+    #[test]
+    fn impossible_amount_without_pennies_fails_fast() {
+        let inventory = drawer(&[(1, 0), (5, 1000), (10, 1000), (25, 1000), (100, 1000)]);
+        assert_eq!(
+            Err(InsufficientInventory {
+                shortfall: USD::new(0, 3)
+            }),
+            USD::new(100, 3).breakdown_with_inventory(&inventory)
+        );
+    }
+
+    #[test]
+    fn impossible_amount_passing_gcd_check_fails_fast() {
+        // Every denomination is a multiple of 5¢, but with only two dimes no
+        // combination reaches the final 5¢ of $1000.05.
+        let inventory = drawer(&[(10, 2), (25, 1000), (100, 1000)]);
+        assert!(USD::new(1000, 5)
+            .breakdown_with_inventory(&inventory)
+            .is_err());
+    }
+
+    #[test]
+    fn zero_needs_nothing() {
+        assert_eq!(
+            Ok(BTreeMap::new()),
+            USD::new(0, 0).breakdown_with_inventory(&BTreeMap::new())
+        );
+    }
 }
@@ -0,0 +1,152 @@
+use crate::currency::USD;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+
+/// Named envelopes that income is allocated into and spending is drawn from.
+#[derive(Debug, Clone, Default)]
+pub struct Envelopes {
+    balances: HashMap<String, USD>,
+}
+
+impl Envelopes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds to an envelope's balance, creating the envelope if needed.
+    pub fn allocate(&mut self, name: &str, amount: USD) -> Result<(), EnvelopeError> {
+        if amount < USD::new(0, 0) {
+            return Err(EnvelopeError::NegativeAmount);
+        }
+
+        let balance = self
+            .balances
+            .entry(name.to_string())
+            .or_insert_with(|| USD::new(0, 0));
+        *balance = &*balance + amount;
+        Ok(())
+    }
+
+    /// Draws from an envelope, leaving it untouched if the amount exceeds its
+    /// balance.
+    pub fn spend(&mut self, name: &str, amount: USD) -> Result<(), EnvelopeError> {
+        if amount < USD::new(0, 0) {
+            return Err(EnvelopeError::NegativeAmount);
+        }
+        let balance = self
+            .balances
+            .get_mut(name)
+            .ok_or(EnvelopeError::UnknownEnvelope)?;
+        if amount > *balance {
+            return Err(EnvelopeError::Overspend {
+                balance: balance.clone(),
+            });
+        }
+
+        *balance = &*balance - amount;
+        Ok(())
+    }
+
+    pub fn balance(&self, name: &str) -> Option<USD> {
+        self.balances.get(name).cloned()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvelopeError {
+    UnknownEnvelope,
+    NegativeAmount,
+    Overspend { balance: USD },
+}
+
+impl Display for EnvelopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvelopeError::UnknownEnvelope => write!(f, "no envelope with that name"),
+            EnvelopeError::NegativeAmount => write!(f, "amount must not be negative"),
+            EnvelopeError::Overspend { balance } => {
+                write!(f, "envelope only has {balance} remaining")
+            }
+        }
+    }
+}
+
+impl Error for EnvelopeError {}
+
+#[cfg(test)]
+mod envelope_tests {
+    use super::*;
+
+    fn groceries_and_fun() -> Envelopes {
+        let mut envelopes = Envelopes::new();
+        envelopes.allocate("groceries", USD::new(300, 0)).unwrap();
+        envelopes.allocate("fun", USD::new(200, 0)).unwrap();
+        envelopes
+    }
+
+    #[test]
+    fn should_track_allocations() {
+        let envelopes = groceries_and_fun();
+        assert_eq!(Some(USD::new(300, 0)), envelopes.balance("groceries"));
+        assert_eq!(Some(USD::new(200, 0)), envelopes.balance("fun"));
+        assert_eq!(None, envelopes.balance("rent"));
+    }
+
+    #[test]
+    fn allocating_again_adds_to_balance() {
+        let mut envelopes = groceries_and_fun();
+        assert_eq!(Ok(()), envelopes.allocate("fun", USD::new(25, 50)));
+        assert_eq!(Some(USD::new(225, 50)), envelopes.balance("fun"));
+    }
+
+    #[test]
+    fn should_spend_within_balance() {
+        let mut envelopes = groceries_and_fun();
+        assert_eq!(Ok(()), envelopes.spend("groceries", USD::new(120, 45)));
+        assert_eq!(Some(USD::new(179, 55)), envelopes.balance("groceries"));
+        assert_eq!(Ok(()), envelopes.spend("fun", USD::new(200, 0)));
+        assert_eq!(Some(USD::new(0, 0)), envelopes.balance("fun"));
+    }
+
+    #[test]
+    fn overspending_errors_and_keeps_balance() {
+        let mut envelopes = groceries_and_fun();
+        assert_eq!(
+            Err(EnvelopeError::Overspend {
+                balance: USD::new(200, 0)
+            }),
+            envelopes.spend("fun", USD::new(200, 1))
+        );
+        assert_eq!(Some(USD::new(200, 0)), envelopes.balance("fun"));
+        assert_eq!(Some(USD::new(300, 0)), envelopes.balance("groceries"));
+    }
+
+    #[test]
+    fn negative_amounts_are_rejected() {
+        let mut envelopes = groceries_and_fun();
+        assert_eq!(
+            Err(EnvelopeError::NegativeAmount),
+            envelopes.spend("fun", USD::new(-50, 0))
+        );
+        assert_eq!(
+            Err(EnvelopeError::NegativeAmount),
+            envelopes.allocate("fun", USD::new(-250, 0))
+        );
+        assert_eq!(
+            Err(EnvelopeError::NegativeAmount),
+            envelopes.allocate("rent", USD::new(-1, 0))
+        );
+        assert_eq!(Some(USD::new(200, 0)), envelopes.balance("fun"));
+        assert_eq!(None, envelopes.balance("rent"));
+    }
+
+    #[test]
+    fn spending_from_unknown_envelope_errors() {
+        let mut envelopes = groceries_and_fun();
+        assert_eq!(
+            Err(EnvelopeError::UnknownEnvelope),
+            envelopes.spend("rent", USD::new(1, 0))
+        );
+    }
+}
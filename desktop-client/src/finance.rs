@@ -0,0 +1,1259 @@
+use crate::currency::{AffordabilityError, USD};
+use crate::rate::Rate;
+use crate::rounding::{div_round, RoundingMode};
+use rug::ops::Pow;
+use rug::{Integer, Rational};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+
+/// The realized tax rate, `tax / income`, or `None` when there is no income.
+///
+/// A negative income (a loss) yields a negative rate rather than an error, so
+/// callers reporting on losses should check the sign.
+pub fn effective_rate(tax: &USD, income: &USD) -> Option<Rate> {
+    if *income.total_cents() == 0 {
+        return None;
+    }
+
+    let rate = Rational::from((tax.total_cents(), income.total_cents()));
+    Some(Rate::from(rate))
+}
+
+/// The balance-weighted average rate across `debts`, or `None` if the balances
+/// sum to zero.
+pub fn blended_rate(debts: &[(USD, Rate)]) -> Option<Rate> {
+    let mut total_balance = Integer::new();
+    let mut weighted = Rational::new();
+    for (balance, rate) in debts {
+        total_balance += balance.total_cents();
+        weighted += Rational::from(rate.value() * balance.total_cents());
+    }
+    if total_balance == 0 {
+        return None;
+    }
+
+    Some(Rate::from(weighted / total_balance))
+}
+
+/// How far above `buy_rate` the sell rate must be to recover a round-trip
+/// `fee`, where the fee is the fraction of the proceeds lost over the round
+/// trip: `buy_rate * fee / (1 - fee)`.
+///
+/// Panics if `fee` is 100%.
+pub fn break_even_spread(buy_rate: &Rate, fee: &Rate) -> Rate {
+    let kept = Rational::from(1 - fee.value());
+    assert!(kept != 0, "a 100% fee can never break even");
+
+    Rate::from(Rational::from(buy_rate.value() * fee.value()) / kept)
+}
+
+/// The first period, up to `max_periods`, at which the balance with the higher
+/// growth rate is at least the other one, compounding both exactly without
+/// rounding. Returns `Some(0)` if it already is, and `None` for equal rates.
+pub fn crossover_period(
+    a_start: &USD,
+    a_rate: &Rate,
+    b_start: &USD,
+    b_rate: &Rate,
+    max_periods: u32,
+) -> Option<u32> {
+    let (fast_start, fast_rate, slow_start, slow_rate) = match a_rate.cmp(b_rate) {
+        Ordering::Greater => (a_start, a_rate, b_start, b_rate),
+        Ordering::Less => (b_start, b_rate, a_start, a_rate),
+        Ordering::Equal => return None,
+    };
+
+    let fast_growth = Rational::from(1 + fast_rate.value());
+    let slow_growth = Rational::from(1 + slow_rate.value());
+    let mut fast = Rational::from(fast_start.total_cents());
+    let mut slow = Rational::from(slow_start.total_cents());
+    for period in 0..=max_periods {
+        if fast >= slow {
+            return Some(period);
+        }
+        fast *= &fast_growth;
+        slow *= &slow_growth;
+    }
+    None
+}
+
+/// Discounts `future` by `(1 + rate)^periods`, rounding once at the end.
+///
+/// Panics if `rate` is -100%.
+pub fn present_value(future: &USD, rate: &Rate, periods: u32, mode: RoundingMode) -> USD {
+    let growth = Rational::from(1 + rate.value());
+    assert!(growth != 0, "cannot discount at a rate of -100%");
+
+    let discount = growth.pow(periods).recip();
+    future.scale(&discount, mode)
+}
+
+/// Net present value of `(period, amount)` cash flows, with each flow
+/// discounted and rounded on its own before summing.
+pub fn npv(rate: &Rate, flows: &[(u32, USD)], mode: RoundingMode) -> USD {
+    flows
+        .iter()
+        .fold(USD::new(0, 0), |total, (period, amount)| {
+            total + present_value(amount, rate, *period, mode)
+        })
+}
+
+/// The contribution needed each period to grow `current` to `goal`.
+///
+/// Use `RoundingMode::Ceiling` to guarantee the goal is reached. A goal that is
+/// already met needs $0.00, even over zero periods; otherwise zero periods is an
+/// error.
+pub fn required_contribution(
+    current: &USD,
+    goal: &USD,
+    periods: u32,
+    mode: RoundingMode,
+) -> Result<USD, ContributionError> {
+    if goal <= current {
+        return Ok(USD::new(0, 0));
+    }
+
+    (goal - current)
+        .unit_price(periods.into(), mode)
+        .map_err(|_| ContributionError::ZeroPeriods)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContributionError {
+    ZeroPeriods,
+}
+
+impl Display for ContributionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContributionError::ZeroPeriods => write!(f, "no periods left to contribute in"),
+        }
+    }
+}
+
+impl Error for ContributionError {}
+
+/// `DecliningBalance` is double-declining: each period writes off `2 / periods`
+/// of the remaining book value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepreciationMethod {
+    StraightLine,
+    DecliningBalance,
+}
+
+/// The depreciation for each of `periods` periods. The last period absorbs any
+/// rounding so the total is exactly `cost - salvage`, and declining balance
+/// never writes off below salvage.
+pub fn depreciate(
+    cost: &USD,
+    salvage: &USD,
+    periods: u32,
+    method: DepreciationMethod,
+    mode: RoundingMode,
+) -> Vec<USD> {
+    let mut schedule = Vec::new();
+    if periods == 0 {
+        return schedule;
+    }
+
+    let zero = USD::new(0, 0);
+    let mut remaining = cost - salvage;
+    let rate = Rational::from((2, periods));
+    for _ in 1..periods {
+        let amount = match method {
+            DepreciationMethod::StraightLine => (cost - salvage)
+                .unit_price(periods.into(), mode)
+                .expect("periods is non-zero"),
+            DepreciationMethod::DecliningBalance => (salvage + &remaining).scale(&rate, mode),
+        };
+        let amount = std::cmp::max(std::cmp::min(amount, remaining.clone()), zero.clone());
+        remaining = &remaining - &amount;
+        schedule.push(amount);
+    }
+    schedule.push(remaining);
+
+    schedule
+}
+
+/// `current` plus the probability-weighted sum of the scenario deltas, rounded
+/// once. Probabilities must be non-negative and sum to at most 100%; any
+/// remainder is the chance that nothing changes.
+pub fn expected_balance(
+    current: &USD,
+    scenarios: &[(Rate, USD)],
+    mode: RoundingMode,
+) -> Result<USD, ProbabilityError> {
+    let mut total_probability = Rational::new();
+    let mut expected_cents = Rational::new();
+    for (probability, delta) in scenarios {
+        if *probability.value() < 0 {
+            return Err(ProbabilityError::NegativeProbability);
+        }
+        total_probability += probability.value();
+        expected_cents += Rational::from(probability.value() * delta.total_cents());
+    }
+    if total_probability > 1 {
+        return Err(ProbabilityError::ExceedsCertainty);
+    }
+
+    Ok(current + USD::from_rational_cents(&expected_cents, mode))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbabilityError {
+    NegativeProbability,
+    ExceedsCertainty,
+}
+
+impl Display for ProbabilityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProbabilityError::NegativeProbability => {
+                write!(f, "probabilities must not be negative")
+            }
+            ProbabilityError::ExceedsCertainty => write!(f, "probabilities sum to more than 100%"),
+        }
+    }
+}
+
+impl Error for ProbabilityError {}
+
+/// The average of the amounts weighted by their paired weights, rounded once,
+/// or `None` if the weights sum to zero.
+pub fn weighted_average(pairs: &[(USD, u64)], mode: RoundingMode) -> Option<USD> {
+    let mut weighted_cents = Integer::new();
+    let mut total_weight = Integer::new();
+    for (amount, weight) in pairs {
+        weighted_cents += amount.total_cents() * Integer::from(*weight);
+        total_weight += *weight;
+    }
+    if total_weight == 0 {
+        return None;
+    }
+
+    let average = Rational::from((weighted_cents, total_weight));
+    Some(USD::from_rational_cents(&average, mode))
+}
+
+/// Months of `monthly_savings` needed to recoup `initial`, rounded up and
+/// saturating at `u32::MAX`. A non-positive cost pays back immediately.
+pub fn payback_period(initial: &USD, monthly_savings: &USD) -> Result<u32, PaybackError> {
+    if *monthly_savings.total_cents() <= 0 {
+        return Err(PaybackError::NonPositiveSavings);
+    }
+    if *initial.total_cents() <= 0 {
+        return Ok(0);
+    }
+
+    let months = div_round(
+        initial.total_cents(),
+        monthly_savings.total_cents(),
+        RoundingMode::Ceiling,
+    );
+    Ok(months.to_u32().unwrap_or(u32::MAX))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaybackError {
+    NonPositiveSavings,
+}
+
+impl Display for PaybackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaybackError::NonPositiveSavings => write!(f, "monthly savings must be positive"),
+        }
+    }
+}
+
+impl Error for PaybackError {}
+
+/// Returns how many periods it takes to pay off `balance` at `payment` per
+/// period, first as-is and then with `extra_now` paid up front. Interest is
+/// charged and rounded each period before the payment is applied.
+/// Errors if the payment isn't positive, any period fails to reduce the
+/// balance, or payoff would take more than `MAX_PAYOFF_PERIODS` periods.
+pub fn payoff_with_extra(
+    balance: &USD,
+    periodic_rate: &Rate,
+    payment: &USD,
+    extra_now: &USD,
+    mode: RoundingMode,
+) -> Result<(u32, u32), PayoffError> {
+    let without_extra = periods_to_payoff(balance.clone(), periodic_rate, payment, mode)?;
+    let with_extra = periods_to_payoff(balance - extra_now, periodic_rate, payment, mode)?;
+    Ok((without_extra, with_extra))
+}
+
+/// The longest payoff `payoff_with_extra` will simulate: 100 years of weekly
+/// payments, rounded up.
+pub const MAX_PAYOFF_PERIODS: u32 = 6_000;
+
+fn periods_to_payoff(
+    mut balance: USD,
+    rate: &Rate,
+    payment: &USD,
+    mode: RoundingMode,
+) -> Result<u32, PayoffError> {
+    let zero = USD::new(0, 0);
+    if *payment <= zero {
+        return Err(PayoffError::NonPositivePayment);
+    }
+
+    let mut periods: u32 = 0;
+    while balance > zero {
+        if periods == MAX_PAYOFF_PERIODS {
+            return Err(PayoffError::TooManyPeriods);
+        }
+        let interest = balance.period_interest(rate, mode);
+        let next = &balance + interest - payment;
+        if next >= balance {
+            return Err(PayoffError::PaymentDoesNotCoverInterest);
+        }
+        balance = next;
+        periods = periods.checked_add(1).ok_or(PayoffError::TooManyPeriods)?;
+    }
+    Ok(periods)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayoffError {
+    NonPositivePayment,
+    PaymentDoesNotCoverInterest,
+    TooManyPeriods,
+}
+
+impl Display for PayoffError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayoffError::NonPositivePayment => write!(f, "payment must be positive"),
+            PayoffError::PaymentDoesNotCoverInterest => {
+                write!(f, "payment does not cover the interest charged")
+            }
+            PayoffError::TooManyPeriods => {
+                write!(f, "payoff takes more than {MAX_PAYOFF_PERIODS} periods")
+            }
+        }
+    }
+}
+
+impl Error for PayoffError {}
+
+/// Whole months `balance` lasts at `monthly_burn`, saturating at `u32::MAX`.
+/// A zero or negative balance has no runway.
+pub fn runway_months(balance: &USD, monthly_burn: &USD) -> Result<u32, RunwayError> {
+    match balance.units_affordable(monthly_burn) {
+        Ok(months) => Ok(u32::try_from(months).unwrap_or(u32::MAX)),
+        Err(AffordabilityError::NegativeBudget) => Ok(0),
+        Err(AffordabilityError::NonPositivePrice) => Err(RunwayError::NonPositiveBurn),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunwayError {
+    NonPositiveBurn,
+}
+
+impl Display for RunwayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunwayError::NonPositiveBurn => write!(f, "monthly burn must be positive"),
+        }
+    }
+}
+
+impl Error for RunwayError {}
+
+/// Sums `amounts`, erroring at the first amount that takes the running total's
+/// magnitude past `max`.
+pub fn sum_bounded(amounts: &[USD], max: &USD) -> Result<USD, BoundExceeded> {
+    let ceiling = max.clone();
+    let floor = -max;
+    let mut sum = USD::new(0, 0);
+    for (index, amount) in amounts.iter().enumerate() {
+        let next = &sum + amount;
+        if next > ceiling || next < floor {
+            return Err(BoundExceeded {
+                index,
+                partial_sum: sum,
+            });
+        }
+        sum = next;
+    }
+    Ok(sum)
+}
+
+/// `partial_sum` is the total of the amounts before `index`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundExceeded {
+    pub index: usize,
+    pub partial_sum: USD,
+}
+
+impl Display for BoundExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "amount at index {} exceeds the bound; sum so far is {}",
+            self.index, self.partial_sum
+        )
+    }
+}
+
+impl Error for BoundExceeded {}
+
+pub const FALLBACK_TIER: &str = "untiered";
+
+/// The label of the first tier whose threshold `amount` meets or exceeds, or
+/// `FALLBACK_TIER` if none do. Tiers must be sorted by descending threshold.
+pub fn classify_tier<'a>(amount: &USD, tiers: &[(USD, &'a str)]) -> &'a str {
+    tiers
+        .iter()
+        .find(|(threshold, _)| amount >= threshold)
+        .map_or(FALLBACK_TIER, |(_, label)| label)
+}
+
+/// Produces (from, to, amount) transfers that bring every balance to zero.
+///
+/// Positive balances are owed money and negative balances owe it. Balances are
+/// expected to sum to zero; largest debts are matched against largest credits
+/// first, which needs at most one fewer transfer than there are people.
+pub fn settle_debts(balances: &HashMap<String, USD>) -> Vec<(String, String, USD)> {
+    let zero = USD::new(0, 0);
+    let mut creditors: Vec<(&String, USD)> = Vec::new();
+    let mut debtors: Vec<(&String, USD)> = Vec::new();
+    for (name, balance) in balances {
+        if *balance > zero {
+            creditors.push((name, balance.clone()));
+        } else if *balance < zero {
+            debtors.push((name, -balance));
+        }
+    }
+
+    let by_amount = |a: &(&String, USD), b: &(&String, USD)| b.1.cmp(&a.1).then(a.0.cmp(b.0));
+    creditors.sort_by(by_amount);
+    debtors.sort_by(by_amount);
+
+    let mut transfers = Vec::new();
+    let (mut c, mut d) = (0, 0);
+    while c < creditors.len() && d < debtors.len() {
+        let amount = std::cmp::min(&creditors[c].1, &debtors[d].1).clone();
+        creditors[c].1 = &creditors[c].1 - &amount;
+        debtors[d].1 = &debtors[d].1 - &amount;
+        transfers.push((debtors[d].0.clone(), creditors[c].0.clone(), amount));
+
+        if creditors[c].1 == zero {
+            c += 1;
+        }
+        if debtors[d].1 == zero {
+            d += 1;
+        }
+    }
+
+    transfers
+}
+
+#[cfg(test)]
+mod effective_rate_tests {
+    use super::*;
+
+    #[test]
+    fn should_divide_tax_by_income() {
+        let rate = effective_rate(&USD::new(15, 0), &USD::new(100, 0));
+        assert_eq!(Some(Rate::percent(15)), rate);
+    }
+
+    #[test]
+    fn should_keep_exact_fraction() {
+        let rate = effective_rate(&USD::new(10, 0), &USD::new(30, 0));
+        assert_eq!(Some(Rate::new(1, 3)), rate);
+    }
+
+    #[test]
+    fn zero_income_has_no_rate() {
+        assert_eq!(None, effective_rate(&USD::new(15, 0), &USD::new(0, 0)));
+    }
+
+    #[test]
+    fn negative_income_gives_negative_rate() {
+        let rate = effective_rate(&USD::new(15, 0), &USD::new(-100, 0));
+        assert_eq!(Some(Rate::percent(-15)), rate);
+    }
+}
+
+#[cfg(test)]
+mod blended_rate_tests {
+    use super::*;
+
+    #[test]
+    fn should_weight_rates_by_balance() {
+        let debts = [
+            (USD::new(1_000, 0), Rate::percent(5)),
+            (USD::new(3_000, 0), Rate::percent(10)),
+        ];
+        assert_eq!(Some(Rate::new(875, 10_000)), blended_rate(&debts));
+    }
+
+    #[test]
+    fn single_debt_keeps_its_rate() {
+        let debts = [(USD::new(250, 0), Rate::new(1, 3))];
+        assert_eq!(Some(Rate::new(1, 3)), blended_rate(&debts));
+    }
+
+    #[test]
+    fn zero_balances_have_no_rate() {
+        let debts = [
+            (USD::new(0, 0), Rate::percent(5)),
+            (USD::new(0, 0), Rate::percent(10)),
+        ];
+        assert_eq!(None, blended_rate(&debts));
+        assert_eq!(None, blended_rate(&[]));
+    }
+}
+
+#[cfg(test)]
+mod break_even_spread_tests {
+    use super::*;
+
+    #[test]
+    fn should_cover_round_trip_fee() {
+        let buy_rate = Rate::new(5, 4);
+        let spread = break_even_spread(&buy_rate, &Rate::percent(1));
+        assert_eq!(Rate::new(5, 396), spread);
+
+        let sell_rate = Rational::from(buy_rate.value() + spread.value());
+        let kept = Rational::from(&sell_rate / buy_rate.value()) * Rational::from((99, 100));
+        assert_eq!(1, kept);
+    }
+
+    #[test]
+    fn zero_fee_needs_no_spread() {
+        assert_eq!(
+            Rate::percent(0),
+            break_even_spread(&Rate::new(5, 4), &Rate::percent(0))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn full_fee_panics() {
+        break_even_spread(&Rate::new(5, 4), &Rate::percent(100));
+    }
+}
+
+#[cfg(test)]
+mod crossover_period_tests {
+    use super::*;
+
+    #[test]
+    fn faster_growth_should_overtake() {
+        let period = crossover_period(
+            &USD::new(1_000, 0),
+            &Rate::percent(10),
+            &USD::new(2_000, 0),
+            &Rate::percent(5),
+            50,
+        );
+        assert_eq!(Some(15), period);
+    }
+
+    #[test]
+    fn argument_order_does_not_matter() {
+        let period = crossover_period(
+            &USD::new(2_000, 0),
+            &Rate::percent(5),
+            &USD::new(1_000, 0),
+            &Rate::percent(10),
+            50,
+        );
+        assert_eq!(Some(15), period);
+    }
+
+    #[test]
+    fn no_crossover_within_cap() {
+        let period = crossover_period(
+            &USD::new(1_000, 0),
+            &Rate::percent(10),
+            &USD::new(2_000, 0),
+            &Rate::percent(5),
+            14,
+        );
+        assert_eq!(None, period);
+    }
+
+    #[test]
+    fn already_ahead_crosses_immediately() {
+        let period = crossover_period(
+            &USD::new(3_000, 0),
+            &Rate::percent(10),
+            &USD::new(2_000, 0),
+            &Rate::percent(5),
+            10,
+        );
+        assert_eq!(Some(0), period);
+    }
+
+    #[test]
+    fn equal_rates_never_cross() {
+        let period = crossover_period(
+            &USD::new(1_000, 0),
+            &Rate::percent(5),
+            &USD::new(2_000, 0),
+            &Rate::percent(5),
+            100,
+        );
+        assert_eq!(None, period);
+    }
+}
+
+#[cfg(test)]
+mod present_value_tests {
+    use super::*;
+
+    #[test]
+    fn should_discount_compounded_amount() {
+        let pv = present_value(
+            &USD::new(1102, 50),
+            &Rate::percent(5),
+            2,
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(USD::new(1000, 0), pv);
+    }
+
+    #[test]
+    fn should_round_once_at_the_end() {
+        let pv = present_value(
+            &USD::new(100, 0),
+            &Rate::percent(10),
+            3,
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(USD::new(75, 13), pv);
+    }
+
+    #[test]
+    fn zero_periods_returns_input() {
+        let future = USD::new(1102, 50);
+        let pv = present_value(&future, &Rate::percent(5), 0, RoundingMode::HalfUp);
+        assert_eq!(future, pv);
+    }
+}
+
+#[cfg(test)]
+mod npv_tests {
+    use super::*;
+
+    #[test]
+    fn should_discount_each_flow() {
+        // 500 / 1.1 = 454.55, 400 / 1.21 = 330.58, 300 / 1.331 = 225.39
+        let flows = [
+            (0, USD::new(-1_000, 0)),
+            (1, USD::new(500, 0)),
+            (2, USD::new(400, 0)),
+            (3, USD::new(300, 0)),
+        ];
+        assert_eq!(
+            USD::new(10, 52),
+            npv(&Rate::percent(10), &flows, RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn zero_rate_sums_flows() {
+        let flows = [(0, USD::new(-100, 0)), (5, USD::new(150, 25))];
+        assert_eq!(
+            USD::new(50, 25),
+            npv(&Rate::percent(0), &flows, RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn no_flows_is_zero() {
+        assert_eq!(
+            USD::new(0, 0),
+            npv(&Rate::percent(10), &[], RoundingMode::HalfUp)
+        );
+    }
+}
+
+#[cfg(test)]
+mod required_contribution_tests {
+    use super::*;
+
+    #[test]
+    fn should_spread_remaining_amount_over_periods() {
+        let contribution = required_contribution(
+            &USD::new(0, 0),
+            &USD::new(1000, 0),
+            10,
+            RoundingMode::Ceiling,
+        );
+        assert_eq!(Ok(USD::new(100, 0)), contribution);
+    }
+
+    #[test]
+    fn ceiling_should_round_up_to_reach_goal() {
+        let contribution =
+            required_contribution(&USD::new(0, 0), &USD::new(100, 0), 3, RoundingMode::Ceiling);
+        assert_eq!(Ok(USD::new(33, 34)), contribution);
+    }
+
+    #[test]
+    fn met_goal_needs_nothing() {
+        let contribution = required_contribution(
+            &USD::new(1200, 0),
+            &USD::new(1000, 0),
+            10,
+            RoundingMode::Ceiling,
+        );
+        assert_eq!(Ok(USD::new(0, 0)), contribution);
+    }
+
+    #[test]
+    fn zero_periods_errors() {
+        let contribution = required_contribution(
+            &USD::new(0, 0),
+            &USD::new(1000, 0),
+            0,
+            RoundingMode::Ceiling,
+        );
+        assert_eq!(Err(ContributionError::ZeroPeriods), contribution);
+    }
+}
+
+#[cfg(test)]
+mod depreciate_tests {
+    use super::*;
+
+    fn total(schedule: &[USD]) -> USD {
+        schedule
+            .iter()
+            .fold(USD::new(0, 0), |sum, amount| sum + amount)
+    }
+
+    #[test]
+    fn straight_line_should_spread_evenly() {
+        let schedule = depreciate(
+            &USD::new(10_000, 0),
+            &USD::new(1_000, 0),
+            5,
+            DepreciationMethod::StraightLine,
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(vec![USD::new(1_800, 0); 5], schedule);
+        assert_eq!(USD::new(9_000, 0), total(&schedule));
+    }
+
+    #[test]
+    fn straight_line_final_period_absorbs_rounding() {
+        let schedule = depreciate(
+            &USD::new(100, 0),
+            &USD::new(0, 0),
+            3,
+            DepreciationMethod::StraightLine,
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(
+            vec![USD::new(33, 33), USD::new(33, 33), USD::new(33, 34)],
+            schedule
+        );
+    }
+
+    #[test]
+    fn declining_balance_should_end_at_salvage() {
+        let cost = USD::new(10_000, 0);
+        let schedule = depreciate(
+            &cost,
+            &USD::new(1_000, 0),
+            5,
+            DepreciationMethod::DecliningBalance,
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(
+            vec![
+                USD::new(4_000, 0),
+                USD::new(2_400, 0),
+                USD::new(1_440, 0),
+                USD::new(864, 0),
+                USD::new(296, 0),
+            ],
+            schedule
+        );
+        assert_eq!(USD::new(1_000, 0), &cost - total(&schedule));
+    }
+
+    #[test]
+    fn declining_balance_stops_at_salvage() {
+        let schedule = depreciate(
+            &USD::new(10_000, 0),
+            &USD::new(5_000, 0),
+            5,
+            DepreciationMethod::DecliningBalance,
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(
+            vec![
+                USD::new(4_000, 0),
+                USD::new(1_000, 0),
+                USD::new(0, 0),
+                USD::new(0, 0),
+                USD::new(0, 0),
+            ],
+            schedule
+        );
+    }
+
+    #[test]
+    fn zero_periods_has_empty_schedule() {
+        let schedule = depreciate(
+            &USD::new(10_000, 0),
+            &USD::new(1_000, 0),
+            0,
+            DepreciationMethod::StraightLine,
+            RoundingMode::HalfUp,
+        );
+        assert!(schedule.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod expected_balance_tests {
+    use super::*;
+
+    #[test]
+    fn should_weight_deltas_by_probability() {
+        let scenarios = [
+            (Rate::percent(75), USD::new(400, 0)),
+            (Rate::percent(25), USD::new(-200, 0)),
+        ];
+        assert_eq!(
+            Ok(USD::new(1_250, 0)),
+            expected_balance(&USD::new(1_000, 0), &scenarios, RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn should_round_weighted_sum_once() {
+        let scenarios = [
+            (Rate::new(1, 3), USD::new(0, 1)),
+            (Rate::new(1, 3), USD::new(0, 1)),
+        ];
+        assert_eq!(
+            Ok(USD::new(0, 1)),
+            expected_balance(&USD::new(0, 0), &scenarios, RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn no_scenarios_keeps_current_balance() {
+        assert_eq!(
+            Ok(USD::new(10, 0)),
+            expected_balance(&USD::new(10, 0), &[], RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn probabilities_over_one_error() {
+        let scenarios = [
+            (Rate::percent(60), USD::new(100, 0)),
+            (Rate::percent(50), USD::new(-100, 0)),
+        ];
+        assert_eq!(
+            Err(ProbabilityError::ExceedsCertainty),
+            expected_balance(&USD::new(0, 0), &scenarios, RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn negative_probability_errors() {
+        let scenarios = [(Rate::percent(-10), USD::new(100, 0))];
+        assert_eq!(
+            Err(ProbabilityError::NegativeProbability),
+            expected_balance(&USD::new(0, 0), &scenarios, RoundingMode::HalfUp)
+        );
+    }
+}
+
+#[cfg(test)]
+mod weighted_average_tests {
+    use super::*;
+
+    #[test]
+    fn should_weight_amounts() {
+        let pairs = [(USD::new(10, 0), 1), (USD::new(20, 0), 3)];
+        assert_eq!(
+            Some(USD::new(17, 50)),
+            weighted_average(&pairs, RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn should_round_average_once() {
+        let pairs = [(USD::new(0, 1), 1), (USD::new(0, 0), 2)];
+        assert_eq!(
+            Some(USD::new(0, 0)),
+            weighted_average(&pairs, RoundingMode::HalfUp)
+        );
+        assert_eq!(
+            Some(USD::new(0, 1)),
+            weighted_average(&pairs, RoundingMode::Ceiling)
+        );
+    }
+
+    #[test]
+    fn zero_weight_amounts_are_ignored() {
+        let pairs = [(USD::new(10, 0), 2), (USD::new(1_000, 0), 0)];
+        assert_eq!(
+            Some(USD::new(10, 0)),
+            weighted_average(&pairs, RoundingMode::HalfUp)
+        );
+    }
+
+    #[test]
+    fn all_zero_weights_has_no_average() {
+        let pairs = [(USD::new(10, 0), 0), (USD::new(20, 0), 0)];
+        assert_eq!(None, weighted_average(&pairs, RoundingMode::HalfUp));
+        assert_eq!(None, weighted_average(&[], RoundingMode::HalfUp));
+    }
+}
+
+#[cfg(test)]
+mod payback_period_tests {
+    use super::*;
+
+    #[test]
+    fn should_divide_cost_by_savings() {
+        assert_eq!(
+            Ok(12),
+            payback_period(&USD::new(1_200, 0), &USD::new(100, 0))
+        );
+    }
+
+    #[test]
+    fn partial_month_rounds_up() {
+        assert_eq!(
+            Ok(13),
+            payback_period(&USD::new(1_250, 0), &USD::new(100, 0))
+        );
+    }
+
+    #[test]
+    fn free_investment_pays_back_immediately() {
+        assert_eq!(Ok(0), payback_period(&USD::new(0, 0), &USD::new(100, 0)));
+    }
+
+    #[test]
+    fn zero_or_negative_savings_errors() {
+        assert_eq!(
+            Err(PaybackError::NonPositiveSavings),
+            payback_period(&USD::new(1_200, 0), &USD::new(0, 0))
+        );
+        assert_eq!(
+            Err(PaybackError::NonPositiveSavings),
+            payback_period(&USD::new(1_200, 0), &USD::new(-100, 0))
+        );
+    }
+}
+
+#[cfg(test)]
+mod payoff_tests {
+    use super::*;
+
+    #[test]
+    fn extra_payment_shortens_timeline() {
+        let periods = payoff_with_extra(
+            &USD::new(1_000, 0),
+            &Rate::percent(1),
+            &USD::new(100, 0),
+            &USD::new(500, 0),
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(Ok((11, 6)), periods);
+    }
+
+    #[test]
+    fn extra_covering_balance_needs_no_periods() {
+        let periods = payoff_with_extra(
+            &USD::new(1_000, 0),
+            &Rate::percent(1),
+            &USD::new(100, 0),
+            &USD::new(1_000, 0),
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(Ok((11, 0)), periods);
+    }
+
+    #[test]
+    fn interest_free_balance_pays_down_linearly() {
+        let periods = payoff_with_extra(
+            &USD::new(1_000, 0),
+            &Rate::percent(0),
+            &USD::new(100, 0),
+            &USD::new(250, 0),
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(Ok((10, 8)), periods);
+    }
+
+    #[test]
+    fn zero_payment_errors_even_with_negative_rate() {
+        let periods = payoff_with_extra(
+            &USD::new(1_000, 0),
+            &Rate::percent(-5),
+            &USD::new(0, 0),
+            &USD::new(0, 0),
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(Err(PayoffError::NonPositivePayment), periods);
+    }
+
+    #[test]
+    fn negative_rate_pays_off_faster() {
+        let periods = payoff_with_extra(
+            &USD::new(1_000, 0),
+            &Rate::percent(-50),
+            &USD::new(100, 0),
+            &USD::new(0, 0),
+            RoundingMode::HalfUp,
+        );
+        // 1000 -> 400 -> 100 -> -50
+        assert_eq!(Ok((3, 3)), periods);
+    }
+
+    #[test]
+    fn payoff_at_period_cap_succeeds() {
+        let periods = payoff_with_extra(
+            &USD::new(60, 0),
+            &Rate::percent(0),
+            &USD::new(0, 1),
+            &USD::new(0, 0),
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(Ok((MAX_PAYOFF_PERIODS, MAX_PAYOFF_PERIODS)), periods);
+    }
+
+    #[test]
+    fn payoff_beyond_period_cap_errors() {
+        let periods = payoff_with_extra(
+            &USD::new(100_000, 0),
+            &Rate::percent(0),
+            &USD::new(0, 1),
+            &USD::new(0, 0),
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(Err(PayoffError::TooManyPeriods), periods);
+
+        let periods = payoff_with_extra(
+            &USD::new(60, 1),
+            &Rate::percent(0),
+            &USD::new(0, 1),
+            &USD::new(0, 0),
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(Err(PayoffError::TooManyPeriods), periods);
+    }
+
+    #[test]
+    fn payment_below_interest_errors() {
+        let periods = payoff_with_extra(
+            &USD::new(10_000, 0),
+            &Rate::percent(2),
+            &USD::new(200, 0),
+            &USD::new(500, 0),
+            RoundingMode::HalfUp,
+        );
+        assert_eq!(Err(PayoffError::PaymentDoesNotCoverInterest), periods);
+    }
+}
+
+#[cfg(test)]
+mod runway_tests {
+    use super::*;
+
+    #[test]
+    fn should_floor_months() {
+        assert_eq!(
+            Ok(4),
+            runway_months(&USD::new(10_000, 0), &USD::new(2_500, 0))
+        );
+        assert_eq!(
+            Ok(3),
+            runway_months(&USD::new(9_999, 99), &USD::new(2_500, 0))
+        );
+    }
+
+    #[test]
+    fn zero_burn_errors() {
+        assert_eq!(
+            Err(RunwayError::NonPositiveBurn),
+            runway_months(&USD::new(10_000, 0), &USD::new(0, 0))
+        );
+    }
+
+    #[test]
+    fn non_positive_balance_has_no_runway() {
+        assert_eq!(
+            Ok(0),
+            runway_months(&USD::new(-500, 0), &USD::new(2_500, 0))
+        );
+        assert_eq!(Ok(0), runway_months(&USD::new(0, 0), &USD::new(2_500, 0)));
+    }
+}
+
+#[cfg(test)]
+mod sum_bounded_tests {
+    use super::*;
+
+    #[test]
+    fn should_sum_within_bounds() {
+        let amounts = [USD::new(40, 0), USD::new(-10, 50), USD::new(70, 50)];
+        assert_eq!(
+            Ok(USD::new(100, 0)),
+            sum_bounded(&amounts, &USD::new(100, 0))
+        );
+    }
+
+    #[test]
+    fn should_report_index_that_trips_bound() {
+        let amounts = [
+            USD::new(40, 0),
+            USD::new(50, 0),
+            USD::new(10, 1),
+            USD::new(-90, 0),
+        ];
+        assert_eq!(
+            Err(BoundExceeded {
+                index: 2,
+                partial_sum: USD::new(90, 0),
+            }),
+            sum_bounded(&amounts, &USD::new(100, 0))
+        );
+    }
+
+    #[test]
+    fn negative_totals_are_bounded_by_magnitude() {
+        let amounts = [USD::new(-60, 0), USD::new(-60, 0)];
+        assert_eq!(
+            Err(BoundExceeded {
+                index: 1,
+                partial_sum: USD::new(-60, 0),
+            }),
+            sum_bounded(&amounts, &USD::new(100, 0))
+        );
+    }
+
+    #[test]
+    fn empty_sum_is_zero() {
+        assert_eq!(Ok(USD::new(0, 0)), sum_bounded(&[], &USD::new(0, 0)));
+    }
+}
+
+#[cfg(test)]
+mod classify_tier_tests {
+    use super::*;
+
+    fn tiers() -> Vec<(USD, &'static str)> {
+        vec![
+            (USD::new(100, 0), "large"),
+            (USD::new(20, 0), "medium"),
+            (USD::new(1, 0), "small"),
+        ]
+    }
+
+    #[test]
+    fn should_classify_into_tiers() {
+        let tiers = tiers();
+        assert_eq!("small", classify_tier(&USD::new(5, 0), &tiers));
+        assert_eq!("medium", classify_tier(&USD::new(50, 0), &tiers));
+        assert_eq!("large", classify_tier(&USD::new(500, 0), &tiers));
+    }
+
+    #[test]
+    fn threshold_belongs_to_its_tier() {
+        let tiers = tiers();
+        assert_eq!("medium", classify_tier(&USD::new(20, 0), &tiers));
+        assert_eq!("small", classify_tier(&USD::new(19, 99), &tiers));
+        assert_eq!("large", classify_tier(&USD::new(100, 0), &tiers));
+    }
+
+    #[test]
+    fn amounts_below_every_tier_fall_back() {
+        assert_eq!(FALLBACK_TIER, classify_tier(&USD::new(0, 99), &tiers()));
+        assert_eq!(FALLBACK_TIER, classify_tier(&USD::new(5, 0), &[]));
+    }
+}
+
+#[cfg(test)]
+mod settle_debts_tests {
+    use super::*;
+
+    fn balances(entries: &[(&str, USD)]) -> HashMap<String, USD> {
+        entries
+            .iter()
+            .map(|(name, amount)| (name.to_string(), amount.clone()))
+            .collect()
+    }
+
+    fn apply(
+        balances: &HashMap<String, USD>,
+        transfers: &[(String, String, USD)],
+    ) -> HashMap<String, USD> {
+        let mut result = balances.clone();
+        for (from, to, amount) in transfers {
+            let payer = result.get_mut(from).unwrap();
+            *payer = &*payer + amount;
+            let payee = result.get_mut(to).unwrap();
+            *payee = &*payee - amount;
+        }
+        result
+    }
+
+    #[test]
+    fn should_settle_three_people() {
+        let b = balances(&[
+            ("alice", USD::new(30, 0)),
+            ("bob", USD::new(-10, 0)),
+            ("carol", USD::new(-20, 0)),
+        ]);
+        let transfers = settle_debts(&b);
+
+        assert_eq!(
+            vec![
+                ("carol".to_string(), "alice".to_string(), USD::new(20, 0)),
+                ("bob".to_string(), "alice".to_string(), USD::new(10, 0)),
+            ],
+            transfers
+        );
+        assert!(apply(&b, &transfers)
+            .values()
+            .all(|balance| *balance == USD::new(0, 0)));
+    }
+
+    #[test]
+    fn total_transferred_equals_sum_of_credits() {
+        let b = balances(&[
+            ("alice", USD::new(12, 50)),
+            ("bob", USD::new(7, 25)),
+            ("carol", USD::new(-19, 75)),
+        ]);
+        let transfers = settle_debts(&b);
+
+        let total = transfers
+            .iter()
+            .fold(USD::new(0, 0), |sum, (_, _, amount)| sum + amount);
+        assert_eq!(USD::new(19, 75), total);
+        assert_eq!(2, transfers.len());
+        assert!(apply(&b, &transfers)
+            .values()
+            .all(|balance| *balance == USD::new(0, 0)));
+    }
+
+    #[test]
+    fn settled_balances_need_no_transfers() {
+        let b = balances(&[("alice", USD::new(0, 0)), ("bob", USD::new(0, 0))]);
+        assert!(settle_debts(&b).is_empty());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod currency;
+pub mod envelope;
+pub mod finance;
+pub mod rate;
+pub mod rounding;
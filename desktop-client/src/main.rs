@@ -1,6 +1,4 @@
-use currency::USD;
-
-mod currency;
+use desktop_client::currency::USD;
 
 fn main() {
     let c1 = USD::new(0, 0);
@@ -0,0 +1,57 @@
+use rug::Rational;
+
+/// An exact rational rate, e.g. an interest rate or a discount.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Rate {
+    value: Rational,
+}
+
+impl Rate {
+    /// Panics if `denominator` is zero.
+    pub fn new(numerator: i64, denominator: u64) -> Self {
+        assert!(denominator != 0, "rate denominator must be non-zero");
+        Self::from(Rational::from((numerator, denominator)))
+    }
+
+    pub fn percent(percent: i64) -> Self {
+        Self::new(percent, 100)
+    }
+
+    pub fn value(&self) -> &Rational {
+        &self.value
+    }
+}
+
+impl From<Rational> for Rate {
+    fn from(value: Rational) -> Self {
+        Self { value }
+    }
+}
+
+#[cfg(test)]
+mod rate_creation_tests {
+    use super::*;
+
+    #[test]
+    fn percent_should_be_fraction_of_100() {
+        assert_eq!(Rate::new(3, 20), Rate::percent(15));
+        assert_eq!(Rational::from((15, 100)), *Rate::percent(15).value());
+    }
+
+    #[test]
+    fn new_should_reduce_fraction() {
+        let r = Rate::new(50, 1000);
+        assert_eq!(Rational::from((1, 20)), *r.value());
+    }
+
+    #[test]
+    fn can_create_negative_rate() {
+        assert!(Rate::percent(-5) < Rate::percent(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_denominator_panics() {
+        Rate::new(1, 0);
+    }
+}
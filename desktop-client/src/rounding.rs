@@ -0,0 +1,118 @@
+use rug::Integer;
+
+/// How to resolve a value that falls between two representable amounts.
+///
+/// The `Half*` modes only differ when the value lies exactly halfway; `HalfUp`
+/// rounds such ties away from zero, `HalfDown` toward zero, and `HalfEven` to
+/// the even neighbour (banker's rounding).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    Floor,
+    Ceiling,
+    TowardZero,
+    AwayFromZero,
+    HalfUp,
+    HalfDown,
+    HalfEven,
+}
+
+/// Divides `numerator` by `denominator`, rounding the quotient with `mode`.
+///
+/// Panics if `denominator` is zero.
+pub fn div_round(numerator: &Integer, denominator: &Integer, mode: RoundingMode) -> Integer {
+    assert!(*denominator != 0, "division by zero");
+
+    let (numerator, denominator) = if *denominator < 0 {
+        (Integer::from(-numerator), Integer::from(-denominator))
+    } else {
+        (numerator.clone(), denominator.clone())
+    };
+
+    let negative = numerator < 0;
+    let (quotient, remainder) = numerator.div_rem_floor(denominator.clone());
+    if remainder == 0 {
+        return quotient;
+    }
+
+    let round_up = match mode {
+        RoundingMode::Floor => false,
+        RoundingMode::Ceiling => true,
+        RoundingMode::TowardZero => negative,
+        RoundingMode::AwayFromZero => !negative,
+        RoundingMode::HalfUp | RoundingMode::HalfDown | RoundingMode::HalfEven => {
+            let twice_remainder = remainder * 2;
+            if twice_remainder < denominator {
+                false
+            } else if twice_remainder > denominator {
+                true
+            } else {
+                match mode {
+                    RoundingMode::HalfUp => !negative,
+                    RoundingMode::HalfDown => negative,
+                    _ => quotient.is_odd(),
+                }
+            }
+        }
+    };
+
+    if round_up {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+#[cfg(test)]
+mod div_round_tests {
+    use super::*;
+
+    fn check(numerator: i64, denominator: i64, mode: RoundingMode, expected: i64) {
+        let result = div_round(&Integer::from(numerator), &Integer::from(denominator), mode);
+        assert_eq!(expected, result, "{numerator}/{denominator} with {mode:?}");
+    }
+
+    #[test]
+    fn exact_division_ignores_mode() {
+        check(10, 5, RoundingMode::Ceiling, 2);
+        check(-10, 5, RoundingMode::Floor, -2);
+    }
+
+    #[test]
+    fn directed_modes_on_positive_values() {
+        check(7, 2, RoundingMode::Floor, 3);
+        check(7, 2, RoundingMode::Ceiling, 4);
+        check(7, 2, RoundingMode::TowardZero, 3);
+        check(7, 2, RoundingMode::AwayFromZero, 4);
+    }
+
+    #[test]
+    fn directed_modes_on_negative_values() {
+        check(-7, 2, RoundingMode::Floor, -4);
+        check(-7, 2, RoundingMode::Ceiling, -3);
+        check(-7, 2, RoundingMode::TowardZero, -3);
+        check(-7, 2, RoundingMode::AwayFromZero, -4);
+    }
+
+    #[test]
+    fn half_modes_on_ties() {
+        check(5, 2, RoundingMode::HalfUp, 3);
+        check(5, 2, RoundingMode::HalfDown, 2);
+        check(5, 2, RoundingMode::HalfEven, 2);
+        check(-5, 2, RoundingMode::HalfUp, -3);
+        check(-5, 2, RoundingMode::HalfDown, -2);
+        check(-3, 2, RoundingMode::HalfEven, -2);
+    }
+
+    #[test]
+    fn half_modes_away_from_ties() {
+        check(7, 3, RoundingMode::HalfDown, 2);
+        check(8, 3, RoundingMode::HalfDown, 3);
+        check(-8, 3, RoundingMode::HalfUp, -3);
+    }
+
+    #[test]
+    fn negative_denominator_is_normalized() {
+        check(7, -2, RoundingMode::Floor, -4);
+        check(-7, -2, RoundingMode::Ceiling, 4);
+    }
+}